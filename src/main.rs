@@ -1,4 +1,9 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Read as _,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 use clap::Parser;
 use eyre::Result;
@@ -6,12 +11,22 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_sarif::sarif::{
     ArtifactContentBuilder, ArtifactLocationBuilder, LocationBuilder, MessageBuilder,
-    PhysicalLocationBuilder, RegionBuilder, ReportingDescriptor, ReportingDescriptorBuilder,
-    Result as SarifResult, ResultBuilder, Run, RunBuilder, Sarif, SarifBuilder, ToolBuilder,
-    ToolComponentBuilder, VersionControlDetails, VersionControlDetailsBuilder,
+    PhysicalLocationBuilder, PropertyBagBuilder, Region, RegionBuilder, ReportingDescriptor,
+    ReportingDescriptorBuilder, Result as SarifResult, ResultBuilder, Run, RunBuilder, Sarif,
+    SarifBuilder, Suppression, SuppressionBuilder, ToolBuilder, ToolComponentBuilder,
+    VersionControlDetails, VersionControlDetailsBuilder,
 };
+use sha2::{Digest, Sha256};
+
+/// Number of lines of surrounding source shown in each result's `contextRegion`.
+const CONTEXT_LINES: u32 = 1;
 
-/// Convert Perl::Critic JSON violations to SARIF
+/// Key under which the content-based fingerprint is stored in each result's
+/// `partialFingerprints`. Versioned so a future change to the hashing scheme can live
+/// alongside it without invalidating fingerprints computed by this one.
+const FINGERPRINT_KEY: &str = "perlCriticSnippet/v1";
+
+/// Convert Perl::Critic violations to SARIF
 ///
 /// Perl::Critic does not ship with a JSON output format, but you can write one trivially
 /// with a simple map over the list of violations.
@@ -34,16 +49,59 @@ use serde_sarif::sarif::{
 ///         diagnostics => $violation->diagnostics,
 ///     };
 /// }
+///
+/// Alternatively, pass `--format native` to feed this tool the output of
+/// `perlcritic --verbose "%f:%l:%c:%s:%p:%m:%e\n"` directly, with no Perl glue required.
+///
+/// Pass `--input` more than once, or point it at a directory, to merge several reports
+/// (e.g. from sharded `perlcritic` runs) into a single SARIF document with de-duplicated
+/// rules and concatenated results.
 #[derive(Debug, Parser)]
 #[command(version, long_about, verbatim_doc_comment)]
 struct Args {
-    /// input file; reads from stdin if not provided
+    /// input file, or directory of input files to merge into one SARIF document; repeat to
+    /// pass several (e.g. `--input a.json --input b.json`); reads from stdin if omitted
     #[clap(short, long)]
-    input: Option<PathBuf>,
+    input: Vec<PathBuf>,
 
     /// output file; writes to stdout if not provided
     #[clap(short, long)]
     output: Option<PathBuf>,
+
+    /// format of the input; auto-detected from the first non-whitespace byte if not provided
+    #[clap(short, long, value_enum)]
+    format: Option<InputFormat>,
+
+    /// directory `filename` in each violation is relative to; when given, regions and
+    /// snippets are built from the real source instead of Perl::Critic's `source` field
+    #[clap(long)]
+    source_root: Option<PathBuf>,
+
+    /// file of previously-accepted violations (matched by content fingerprint); matching
+    /// results are still emitted but carry an accepted `external` suppression
+    #[clap(long)]
+    baseline: Option<PathBuf>,
+
+    /// regenerate --baseline from the violations in this run and exit, instead of converting;
+    /// no SARIF document is emitted
+    #[clap(long)]
+    write_baseline: bool,
+}
+
+/// Violations that have already been triaged, recorded by content fingerprint (see
+/// [`partial_fingerprint`]) rather than by filename/line so the file doesn't churn as code
+/// moves around. Written by `--write-baseline`, read by `--baseline`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    fingerprints: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum InputFormat {
+    /// the bespoke `PerlCriticReport` JSON described above
+    Json,
+    /// `perlcritic --verbose "%f:%l:%c:%s:%p:%m:%e\n"` output
+    Native,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,22 +125,204 @@ struct Violation {
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let input: Box<dyn std::io::Read> = match args.input {
-        Some(path) => Box::new(std::fs::File::open(path)?),
-        None => Box::new(std::io::stdin()),
+
+    let inputs = expand_inputs(&args.input)?;
+    let report = if inputs.is_empty() {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        parse_report(&buf, args.format)?
+    } else {
+        let reports = inputs
+            .iter()
+            .map(|path| read_report(path, args.format))
+            .collect::<Result<Vec<_>>>()?;
+        merge_reports(reports)
+    };
+
+    if args.write_baseline {
+        let path = args
+            .baseline
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("--write-baseline requires --baseline <file>"))?;
+        let baseline = compute_baseline(&report, args.source_root.as_deref())?;
+        write_baseline(path, &baseline)?;
+        return Ok(());
+    }
+
+    let baseline = match &args.baseline {
+        Some(path) => Some(load_baseline(path)?),
+        None => None,
     };
+
     let output: Box<dyn std::io::Write> = match args.output {
         Some(path) => Box::new(std::fs::File::create(path)?),
         None => Box::new(std::io::stdout()),
     };
 
-    let report: PerlCriticReport = serde_json::from_reader(input)?;
-    let sarif: Sarif = report.try_into()?;
+    let sarif = report_to_sarif(&report, args.source_root.as_deref(), baseline.as_ref())?;
     serde_json::to_writer(output, &sarif)?;
 
     Ok(())
 }
 
+/// Expand each `--input` argument into a flat list of files, so a path naming a directory
+/// (e.g. one shard's output from a parallel `perlcritic` run) pulls in every file inside it.
+///
+/// Dotfiles (`.gitkeep`, `.DS_Store`, ...) are skipped, since shard directories commonly pick
+/// those up without them being reports to parse.
+fn expand_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for path in inputs {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(path)?
+                .map(|entry| Ok(entry?.path()))
+                .collect::<Result<Vec<_>>>()?;
+            entries.retain(|entry| {
+                entry.is_file()
+                    && !entry
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with('.'))
+            });
+            entries.sort();
+            expanded.extend(entries);
+        } else {
+            expanded.push(path.clone());
+        }
+    }
+    Ok(expanded)
+}
+
+fn parse_report(buf: &str, format: Option<InputFormat>) -> Result<PerlCriticReport> {
+    let format = format.unwrap_or_else(|| detect_format(buf));
+    Ok(match format {
+        InputFormat::Json => serde_json::from_str(buf)?,
+        InputFormat::Native => parse_native_report(buf)?,
+    })
+}
+
+fn read_report(path: &Path, format: Option<InputFormat>) -> Result<PerlCriticReport> {
+    parse_report(&std::fs::read_to_string(path)?, format)
+}
+
+/// Fold several `perlcritic` reports into one, concatenating their violations and preserving
+/// every distinct `perl_critic_version` that contributed to the run. Rule de-duplication
+/// falls out of this for free: `PerlCriticReport::rules` already de-dupes by `policy_to_id`
+/// over `self.violations`, so once the violations are merged here, so are the rules.
+fn merge_reports(reports: Vec<PerlCriticReport>) -> PerlCriticReport {
+    let mut versions = reports
+        .iter()
+        .map(|report| report.perl_critic_version.clone())
+        .collect::<Vec<_>>();
+    versions.sort();
+    versions.dedup();
+
+    PerlCriticReport {
+        perl_critic_version: versions.join(", "),
+        violations: reports
+            .into_iter()
+            .flat_map(|report| report.violations)
+            .collect(),
+    }
+}
+
+/// Compute the content fingerprint of every violation in `report`, for writing out as a
+/// `--baseline` file.
+fn compute_baseline(report: &PerlCriticReport, source_root: Option<&Path>) -> Result<Baseline> {
+    let fingerprints = report
+        .violations
+        .iter()
+        .map(|v| {
+            let lines = source_root
+                .map(|root| read_source_lines(&root.join(&v.filename)))
+                .transpose()?;
+            Ok(partial_fingerprint(v, lines.as_deref()))
+        })
+        .collect::<Result<BTreeSet<_>>>()?;
+    Ok(Baseline { fingerprints })
+}
+
+fn load_baseline(path: &Path) -> Result<Baseline> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn write_baseline(path: &Path, baseline: &Baseline) -> Result<()> {
+    let contents = serde_json::to_string_pretty(baseline)?;
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Sniff the first non-whitespace byte of the input to decide which parser to use.
+///
+/// `perlcritic`'s native output never starts a line with `{`, so this is sufficient to
+/// distinguish it from the JSON format.
+fn detect_format(input: &str) -> InputFormat {
+    match input.trim_start().chars().next() {
+        Some('{') => InputFormat::Json,
+        _ => InputFormat::Native,
+    }
+}
+
+/// Parse the output of `perlcritic --verbose "%f:%l:%c:%s:%p:%m:%e\n"`.
+fn parse_native_report(input: &str) -> Result<PerlCriticReport> {
+    let violations = input
+        .lines()
+        .filter_map(parse_native_line)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PerlCriticReport {
+        perl_critic_version: "unknown".to_string(),
+        violations,
+    })
+}
+
+/// Matches the fixed-count `%f:%l:%c:%s:%p:%m:%e` shape up through the policy field — the
+/// policy package name is matched explicitly (it only ever contains paired `::`, never a
+/// lone `:`), so the trailing `rest` capture is unambiguously "message:explanation" and is
+/// left untouched by this regex; splitting that apart is left to `parse_native_line` so that
+/// colons inside either field don't make the whole line fail to match.
+fn native_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(?P<filename>[^:]+):(?P<line>\d+):(?P<column>\d+):(?P<severity>\d+):(?P<policy>[A-Za-z0-9_]+(?:::[A-Za-z0-9_]+)*):(?P<rest>.*)$",
+        )
+        .expect("static regex is valid")
+    })
+}
+
+/// Parse a single line of `perlcritic --verbose` output into a [`Violation`].
+///
+/// Returns `None` for blank lines and any line that doesn't match the expected shape, such
+/// as the trailing summary line `perlcritic` prints after the violations.
+fn parse_native_line(line: &str) -> Option<Result<Violation>> {
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    let caps = native_line_regex().captures(line)?;
+    // message and explanation are the last two of the fixed 7 fields; splitting at the
+    // final colon keeps any colons inside the message intact instead of rejecting the line.
+    let (message, explanation) = caps["rest"].rsplit_once(':').unwrap_or((&caps["rest"], ""));
+
+    let violation = (|| -> Result<Violation> {
+        Ok(Violation {
+            filename: caps["filename"].to_string(),
+            line_number: caps["line"].parse()?,
+            column_number: caps["column"].parse()?,
+            severity: caps["severity"].parse()?,
+            source: String::new(),
+            diagnostics: message.to_string(),
+            explanation: explanation.to_string(),
+            description: message.to_string(),
+            policy: caps["policy"].to_string(),
+        })
+    })();
+
+    Some(violation)
+}
+
 impl PerlCriticReport {
     fn rules(&self) -> Result<Vec<ReportingDescriptor>> {
         let rules = self
@@ -136,90 +376,288 @@ fn policy_to_name(policy: &str) -> String {
     policy.split("::").skip(4).collect::<Vec<_>>().join("")
 }
 
-impl TryFrom<Violation> for SarifResult {
-    type Error = eyre::Report;
+/// Read `path` and split it into lines for building regions/snippets.
+fn read_source_lines(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents.lines().map(str::to_string).collect())
+}
 
-    fn try_from(v: Violation) -> Result<Self> {
-        let level = match v.severity {
-            5 => "error",
-            4 => "warning",
-            3 => "note",
-            _ => "none",
+/// Build the `contextRegion`/`region` pair for a violation.
+///
+/// When `lines` is `Some` (i.e. `--source-root` was given and the file could be read), the
+/// regions are computed from the real source: line/column counts are exact and the snippets
+/// are the actual text. The context window is clamped to the file's boundaries, so a
+/// violation on the first or last line doesn't underflow or run past EOF. Columns are counted
+/// in Unicode code points, matching Perl::Critic's own `column_number` and the run's
+/// `columnKind` of `unicodeCodePoints` — the run must not mix this with byte/UTF-16 counts.
+///
+/// Otherwise this falls back to Perl::Critic's own `source` field, which only ever carries
+/// the single offending line.
+fn violation_regions(v: &Violation, lines: Option<&[String]>) -> Result<(Region, Region)> {
+    match lines {
+        Some(lines) if !lines.is_empty() => {
+            let total_lines = lines.len() as u32;
+            // A violation can be reported past the last line (a stale `--source-root`, or an
+            // end-of-file policy like `RequireEndWithOne`), so the window is anchored to the
+            // last real line instead of slicing past `total_lines`.
+            let anchor_line = v.line_number.min(total_lines);
+            let start_line = anchor_line.saturating_sub(CONTEXT_LINES).max(1);
+            let end_line = (anchor_line + CONTEXT_LINES).min(total_lines).max(start_line);
+            let context_lines = &lines[(start_line - 1) as usize..end_line as usize];
+            let context_snippet = context_lines.join("\n");
+            let context_end_column =
+                context_lines.last().map_or(1, |line| line.chars().count() as u32 + 1);
+
+            let violation_line =
+                lines.get(anchor_line.saturating_sub(1) as usize).map_or("", String::as_str);
+
+            let context_region = RegionBuilder::default()
+                .start_line(start_line)
+                .start_column(1)
+                .end_line(end_line)
+                .end_column(context_end_column)
+                .snippet(ArtifactContentBuilder::default().text(context_snippet).build()?)
+                .build()?;
+
+            let region = RegionBuilder::default()
+                .start_line(v.line_number)
+                .start_column(v.column_number)
+                .end_line(v.line_number)
+                .end_column(violation_line.chars().count() as u32 + 1)
+                .snippet(ArtifactContentBuilder::default().text(violation_line).build()?)
+                .build()?;
+
+            Ok((context_region, region))
+        }
+        // `lines` is `None` (no `--source-root`), or `Some(&[])` (an empty file read from
+        // `--source-root`) — either way there's no real source to slice, so fall back to
+        // Perl::Critic's own `source` field.
+        _ => {
+            let start_line = v.line_number.saturating_sub(CONTEXT_LINES).max(1);
+            let context_region = RegionBuilder::default()
+                .start_line(start_line)
+                .start_column(1)
+                .end_line(v.line_number + CONTEXT_LINES)
+                .end_column(1)
+                .snippet(ArtifactContentBuilder::default().text(&v.source).build()?)
+                .build()?;
+
+            // Native-format violations never carry `source`, so `end_column` would otherwise
+            // come out as 0 and precede `start_column`; fall back to `column_number` so the
+            // region stays non-degenerate.
+            let source_len = v.source.chars().count() as u32;
+            let region = RegionBuilder::default()
+                .start_line(v.line_number)
+                .start_column(v.column_number)
+                .end_line(v.line_number)
+                .end_column(if source_len == 0 { v.column_number } else { source_len })
+                .snippet(ArtifactContentBuilder::default().text(&v.source).build()?)
+                .build()?;
+
+            Ok((context_region, region))
         }
-        .to_string();
-        let location = LocationBuilder::default()
-            .physical_location(
-                PhysicalLocationBuilder::default()
-                    .artifact_location(
-                        ArtifactLocationBuilder::default()
-                            .uri(format!("project/{}", v.filename))
-                            .uri_base_id("PROJECT")
-                            .build()?,
-                    )
-                    .context_region(
-                        RegionBuilder::default()
-                            .start_line(v.line_number - 1)
-                            .start_column(1)
-                            .end_line(v.line_number + 1)
-                            .end_column(1)
-                            .snippet(ArtifactContentBuilder::default().text(&v.source).build()?)
-                            .build()?,
-                    )
-                    .region(
-                        RegionBuilder::default()
-                            .start_line(v.line_number)
-                            .start_column(v.column_number)
-                            .end_line(v.line_number)
-                            .end_column(v.source.len() as u32)
-                            .snippet(ArtifactContentBuilder::default().text(&v.source).build()?)
-                            .build()?,
-                    )
-                    .build()?,
-            )
-            .build()?;
-
-        Ok(ResultBuilder::default()
-            .message(MessageBuilder::default().text(v.diagnostics).build()?)
-            .level(level)
-            .rule_id(policy_to_id(&v.policy))
-            .locations(vec![location])
-            .build()?)
     }
 }
 
-impl TryFrom<PerlCriticReport> for Run {
-    type Error = eyre::Report;
-
-    fn try_from(report: PerlCriticReport) -> Result<Self> {
-        Ok(RunBuilder::default()
-            .tool(
-                ToolBuilder::default()
-                    .driver(
-                        ToolComponentBuilder::default()
-                            .name("Perl Critic")
-                            .full_name("Perl::Critic")
-                            .version(&report.perl_critic_version)
-                            .information_uri("https://metacpan.org/pod/Perl::Critic")
-                            .rules(report.rules()?)
-                            .build()?,
-                    )
-                    .build()?,
-            )
-            .version_control_provenance(version_control_provenance()?)
-            .results(
-                report
-                    .violations
-                    .clone()
-                    .into_iter()
-                    .map(|v| v.try_into())
-                    .collect::<Result<Vec<_>>>()?,
-            )
-            .build()?)
+/// Compute a stable, content-based fingerprint for a violation so that GitHub code scanning
+/// can track the same alert across commits that shift line numbers around, the way
+/// `cargo-vet` keeps exemptions attached to a crate's audited content rather than its
+/// position in a changelog.
+///
+/// Hashes the filename, the rule id, the violation's own (trimmed) line, and a small window
+/// of the surrounding source with each line's leading indentation stripped, so that
+/// re-indenting or inserting lines elsewhere in the file doesn't change the fingerprint. The
+/// raw line number is deliberately left out of the hash.
+///
+/// Without `--source-root`, Perl::Critic's own `source` field stands in for the line and the
+/// window; when that is empty too (a native violation, which never carries `source`), the
+/// violation's own diagnostic message does. In both cases the column is folded in as well,
+/// so that distinct violations of the same policy don't all collapse onto one fingerprint.
+fn partial_fingerprint(v: &Violation, lines: Option<&[String]>) -> String {
+    // `extra_column` is only `Some` in the sourceless branches below: when real source is
+    // available the snippet+window is already enough to tell distinct violations apart, and
+    // folding the column in there too would make the fingerprint change on a mere re-indent.
+    let (violation_line, window, extra_column) = match lines {
+        Some(lines) if !lines.is_empty() => {
+            let total_lines = lines.len() as u32;
+            // See the matching comment in `violation_regions`: anchor to the last real line
+            // instead of slicing past `total_lines` when `line_number` overruns EOF.
+            let anchor_line = v.line_number.min(total_lines);
+            let start_line = anchor_line.saturating_sub(CONTEXT_LINES).max(1);
+            let end_line = (anchor_line + CONTEXT_LINES).min(total_lines).max(start_line);
+            let violation_line = lines
+                .get(anchor_line.saturating_sub(1) as usize)
+                .map_or("", String::as_str);
+            let window = lines[(start_line - 1) as usize..end_line as usize]
+                .iter()
+                .map(|line| line.trim_start())
+                .collect::<Vec<_>>()
+                .join("\n");
+            (violation_line, window, None)
+        }
+        _ if !v.source.is_empty() => {
+            (v.source.as_str(), v.source.trim_start().to_string(), Some(v.column_number))
+        }
+        _ => (v.diagnostics.as_str(), v.diagnostics.trim_start().to_string(), Some(v.column_number)),
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(v.filename.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(policy_to_id(&v.policy).as_bytes());
+    hasher.update([0u8]);
+    hasher.update(violation_line.trim().as_bytes());
+    hasher.update([0u8]);
+    hasher.update(window.as_bytes());
+    if let Some(column) = extra_column {
+        hasher.update([0u8]);
+        hasher.update(column.to_be_bytes());
     }
+    format!("{:x}", hasher.finalize())
 }
 
-fn version_control_provenance() -> Result<Vec<VersionControlDetails>> {
+fn violation_to_result<'repo>(
+    v: &Violation,
+    source_root: Option<&Path>,
+    baseline: Option<&Baseline>,
+    repo: &'repo git2::Repository,
+    blame_cache: &mut HashMap<String, git2::Blame<'repo>>,
+) -> Result<SarifResult> {
+    let level = match v.severity {
+        5 => "error",
+        4 => "warning",
+        3 => "note",
+        _ => "none",
+    }
+    .to_string();
+
+    let lines = source_root
+        .map(|root| read_source_lines(&root.join(&v.filename)))
+        .transpose()?;
+    let (context_region, region) = violation_regions(v, lines.as_deref())?;
+    let fingerprint = partial_fingerprint(v, lines.as_deref());
+    let partial_fingerprints = HashMap::from([(FINGERPRINT_KEY.to_string(), fingerprint.clone())]);
+
+    let suppressions = if baseline.is_some_and(|b| b.fingerprints.contains(&fingerprint)) {
+        vec![SuppressionBuilder::default()
+            .kind("external")
+            .status("accepted")
+            .build()?]
+    } else {
+        Vec::<Suppression>::new()
+    };
+
+    let location = LocationBuilder::default()
+        .physical_location(
+            PhysicalLocationBuilder::default()
+                .artifact_location(
+                    ArtifactLocationBuilder::default()
+                        .uri(format!("project/{}", v.filename))
+                        .uri_base_id("PROJECT")
+                        .build()?,
+                )
+                .context_region(context_region)
+                .region(region)
+                .build()?,
+        )
+        .build()?;
+
+    let blame = blame_for_line(repo, blame_cache, &v.filename, v.line_number)?;
+
+    let mut result = ResultBuilder::default();
+    result
+        .message(MessageBuilder::default().text(&v.diagnostics).build()?)
+        .level(level)
+        .rule_id(policy_to_id(&v.policy))
+        .partial_fingerprints(partial_fingerprints)
+        .locations(vec![location]);
+    if !suppressions.is_empty() {
+        result.suppressions(suppressions);
+    }
+    if let Some(blame) = blame {
+        result.properties(
+            PropertyBagBuilder::default()
+                .additional_properties(HashMap::from([("gitBlame".to_string(), blame)]))
+                .build()?,
+        );
+    }
+
+    Ok(result.build()?)
+}
+
+fn report_to_run(
+    report: &PerlCriticReport,
+    source_root: Option<&Path>,
+    baseline: Option<&Baseline>,
+) -> Result<Run> {
     let repo = git2::Repository::open_from_env()?;
+    let mut blame_cache = HashMap::new();
+
+    Ok(RunBuilder::default()
+        .column_kind("unicodeCodePoints")
+        .tool(
+            ToolBuilder::default()
+                .driver(
+                    ToolComponentBuilder::default()
+                        .name("Perl Critic")
+                        .full_name("Perl::Critic")
+                        .version(&report.perl_critic_version)
+                        .information_uri("https://metacpan.org/pod/Perl::Critic")
+                        .rules(report.rules()?)
+                        .build()?,
+                )
+                .build()?,
+        )
+        .version_control_provenance(version_control_provenance(&repo)?)
+        .results(
+            report
+                .violations
+                .iter()
+                .map(|v| violation_to_result(v, source_root, baseline, &repo, &mut blame_cache))
+                .collect::<Result<Vec<_>>>()?,
+        )
+        .build()?)
+}
+
+/// Attribute the line a violation was reported on to the commit that last touched it, the
+/// same way reviewers resolve committer identity from the repository via `git blame` rather
+/// than trusting a name baked into the report.
+///
+/// Blames a file at most once per run when blaming succeeds: the first violation in a file
+/// runs `git2::blame_file` and subsequent violations in the same file reuse it from `cache`.
+/// A failed blame is not cached, so it is tried again for each violation in that file.
+fn blame_for_line<'repo>(
+    repo: &'repo git2::Repository,
+    cache: &mut HashMap<String, git2::Blame<'repo>>,
+    filename: &str,
+    line_number: u32,
+) -> Result<Option<serde_json::Value>> {
+    if !cache.contains_key(filename) {
+        let blame = match repo.blame_file(Path::new(filename), None) {
+            Ok(blame) => blame,
+            Err(_) => return Ok(None),
+        };
+        cache.insert(filename.to_string(), blame);
+    }
+
+    let blame = cache.get(filename).expect("just inserted above");
+    let Some(hunk) = blame.get_line(line_number as usize) else {
+        return Ok(None);
+    };
+
+    let commit_id = hunk.final_commit_id();
+    let commit = repo.find_commit(commit_id)?;
+    let author = hunk.final_signature();
+
+    Ok(Some(serde_json::json!({
+        "commit": commit_id.to_string(),
+        "authorName": author.name().unwrap_or_default(),
+        "authorEmail": author.email().unwrap_or_default(),
+        "commitTime": commit.time().seconds(),
+    })))
+}
+
+fn version_control_provenance(repo: &git2::Repository) -> Result<Vec<VersionControlDetails>> {
     let repo_url = git_remote_to_public_url(&repo.config()?.get_string("remote.origin.url")?)?;
     let head = repo.head()?;
     let branch = head.shorthand().unwrap_or("(detached head)");
@@ -256,15 +694,90 @@ fn git_remote_to_public_url(remote: &str) -> Result<String> {
     }
 }
 
-impl TryFrom<PerlCriticReport> for Sarif {
-    type Error = eyre::Report;
+fn report_to_sarif(
+    report: &PerlCriticReport,
+    source_root: Option<&Path>,
+    baseline: Option<&Baseline>,
+) -> Result<Sarif> {
+    Ok(SarifBuilder::default()
+        .runs(vec![report_to_run(report, source_root, baseline)?])
+        .schema("https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json".to_string())
+        .version("2.1.0")
+        .build()
+        ?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(line_number: u32, column_number: u32) -> Violation {
+        Violation {
+            filename: "lib/Foo.pm".to_string(),
+            line_number,
+            column_number,
+            severity: 3,
+            source: String::new(),
+            diagnostics: "diag".to_string(),
+            explanation: "explanation".to_string(),
+            description: "diag".to_string(),
+            policy: "Perl::Critic::Policy::Foo".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_native_line_keeps_colons_in_message() {
+        let line = "lib/Foo.pm:10:4:3:Perl::Critic::Policy::Modules::Foo:\
+                     Use of 'eval': string form is error-prone:Explanation here";
+        let violation = parse_native_line(line)
+            .expect("line should match the native format")
+            .expect("line should parse into a Violation");
+
+        assert_eq!(violation.filename, "lib/Foo.pm");
+        assert_eq!(violation.line_number, 10);
+        assert_eq!(violation.column_number, 4);
+        assert_eq!(violation.policy, "Perl::Critic::Policy::Modules::Foo");
+        // The message itself contains a colon; only the final colon (before the explanation)
+        // should be treated as a field separator.
+        assert_eq!(violation.diagnostics, "Use of 'eval': string form is error-prone");
+        assert_eq!(violation.explanation, "Explanation here");
+    }
+
+    #[test]
+    fn violation_regions_does_not_underflow_at_line_one() {
+        let lines = [
+            "use strict;".to_string(),
+            "use warnings;".to_string(),
+            "1;".to_string(),
+        ];
+        let v = violation(1, 1);
+
+        let (context, region) =
+            violation_regions(&v, Some(&lines)).expect("regions should build for the first line");
+        let context = serde_json::to_value(context).unwrap();
+        let region = serde_json::to_value(region).unwrap();
+
+        assert_eq!(context["startLine"], 1);
+        assert_eq!(region["startLine"], 1);
+    }
+
+    #[test]
+    fn violation_regions_clamps_at_eof_instead_of_panicking() {
+        let lines = [
+            "use strict;".to_string(),
+            "use warnings;".to_string(),
+            "1;".to_string(),
+        ];
+        // A stale --source-root (or an end-of-file policy) can report a line number past the
+        // end of the file; this must clamp instead of slicing out of bounds.
+        let v = violation(100, 1);
+
+        let (context, region) = violation_regions(&v, Some(&lines))
+            .expect("regions should build without panicking past EOF");
+        let context = serde_json::to_value(context).unwrap();
 
-    fn try_from(report: PerlCriticReport) -> Result<Self> {
-        Ok(SarifBuilder::default()
-            .runs(vec![report.try_into()?])
-            .schema("https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json".to_string())
-            .version("2.1.0")
-            .build()
-            ?)
+        assert_eq!(context["endLine"], 3);
+        // The region itself still reports the violation's own (out-of-range) line number.
+        assert_eq!(region["startLine"], 100);
     }
 }